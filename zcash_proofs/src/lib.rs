@@ -8,9 +8,10 @@
 
 use bellman::groth16::{prepare_verifying_key, Parameters, PreparedVerifyingKey, VerifyingKey};
 use pairing::bls12_381::Bls12;
+use std::fmt;
 use std::fs::File;
 use std::io::{self, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub mod circuit;
 mod hashreader;
@@ -20,6 +21,157 @@ pub mod sprout;
 #[cfg(feature = "local-prover")]
 pub mod prover;
 
+// Sapling circuit hashes
+const SAPLING_SPEND_HASH: &str = "25fd9a0d1c1be0526c14662947ae95b758fe9f3d7fb7f55e9b4437830dcc6215a7ce3ea465914b157715b7a4d681389ea4aa84438190e185d5e4c93574d3a19a";
+const SAPLING_OUTPUT_HASH: &str = "a1cb23b93256adce5bce2cb09cefbc96a1d16572675ceb691e9a3626ec15b5b546926ff1c536cfe3a9df07d796b32fdfc3e5d99d65567257bf286cd2858d71a6";
+const SPROUT_HASH: &str = "_";
+
+/// The BLAKE2b digests that a set of Sapling spend and output parameters, and Sprout
+/// parameters, must match before they are trusted.
+///
+/// [`ExpectedHashes::default`] (equivalently [`ExpectedHashes::mainnet`]) is the current
+/// mainnet parameter set. Custom digests can be supplied instead to validate an
+/// alternative parameter set, such as a smaller test-circuit used by regtest or
+/// integration tests, without recompiling this crate.
+///
+/// The `_with_hashes` functions that accept an `ExpectedHashes` (e.g.
+/// [`parse_parameters_with_hashes`], [`try_load_verifying_keys_with_hashes`]) are thin
+/// wrappers around the same [`parse_groth16_params`] core their hash-less counterparts
+/// use, so supporting custom digests doesn't add a second copy of the parsing or
+/// hash-checking logic to keep in sync.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExpectedHashes {
+    pub spend: &'static str,
+    pub output: &'static str,
+    pub sprout: &'static str,
+}
+
+impl Default for ExpectedHashes {
+    fn default() -> Self {
+        ExpectedHashes::mainnet()
+    }
+}
+
+impl ExpectedHashes {
+    /// The hashes of the Sapling spend and output parameters currently distributed for
+    /// Zcash mainnet. The Sprout entry is the placeholder `"_"`, which no digest
+    /// matches, so a Sprout parameter file fails the hash check under these hashes.
+    pub fn mainnet() -> Self {
+        ExpectedHashes {
+            spend: SAPLING_SPEND_HASH,
+            output: SAPLING_OUTPUT_HASH,
+            sprout: SPROUT_HASH,
+        }
+    }
+}
+
+/// Identifies which parameter file a [`ParameterError::InvalidHash`] refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParameterKind {
+    SaplingSpend,
+    SaplingOutput,
+    Sprout,
+}
+
+impl fmt::Display for ParameterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParameterKind::SaplingSpend => "Sapling spend",
+            ParameterKind::SaplingOutput => "Sapling output",
+            ParameterKind::Sprout => "Sprout groth16",
+        })
+    }
+}
+
+/// Errors that can occur while loading or parsing Groth16 parameters.
+#[derive(Debug)]
+pub enum ParameterError {
+    /// An I/O error occurred while reading a parameter source.
+    Io(io::Error),
+    /// The parameter bytes could not be deserialized as a Groth16 parameter (or
+    /// verifying key) file.
+    Deserialization(io::Error),
+    /// A parameter source did not hash to the digest it was expected to.
+    InvalidHash {
+        kind: ParameterKind,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterError::Io(e) => write!(f, "couldn't load parameters: {}", e),
+            ParameterError::Deserialization(e) => {
+                write!(f, "couldn't deserialize parameters: {}", e)
+            }
+            ParameterError::InvalidHash {
+                kind,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} parameter data is not correct (expected hash {}, found {})",
+                kind, expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
+
+/// The Groth16 parameters and prepared verifying keys produced by
+/// [`try_load_parameters`] and [`parse_parameters`].
+pub type LoadedParameters = (
+    Parameters<Bls12>,
+    PreparedVerifyingKey<Bls12>,
+    Parameters<Bls12>,
+    PreparedVerifyingKey<Bls12>,
+    Option<PreparedVerifyingKey<Bls12>>,
+);
+
+/// Returns the default folder used by `zcashd`/`zcash-cli` to store the Sapling and
+/// Sprout Groth16 parameters, if it can be determined for the current platform.
+///
+/// This is `%APPDATA%\ZcashParams` on Windows, and `~/.zcash-params` (honouring the
+/// `HOME` environment variable) elsewhere.
+///
+/// This only consults the `HOME`/`APPDATA` environment variables, not the OS user
+/// database (e.g. `getpwuid` on Unix), so it returns `None` on setups (some containers
+/// and services) where the home directory is configured outside the environment.
+pub fn default_params_folder() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("APPDATA").map(|appdata| PathBuf::from(appdata).join("ZcashParams"))
+    } else {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".zcash-params"))
+    }
+}
+
+/// Loads the Sapling and Sprout Groth16 parameters from the default per-platform
+/// parameters directory returned by [`default_params_folder`].
+pub fn load_default_parameters() -> LoadedParameters {
+    try_load_default_parameters().expect("couldn't load Sapling and Sprout groth16 parameters")
+}
+
+/// Like [`load_default_parameters`], but returns an error rather than panicking if the
+/// default parameters directory can't be determined for this platform, or if loading
+/// from it fails.
+pub fn try_load_default_parameters() -> Result<LoadedParameters, ParameterError> {
+    let params_dir = default_params_folder().ok_or_else(|| {
+        ParameterError::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            "couldn't determine the default Zcash parameters directory for this platform",
+        ))
+    })?;
+
+    try_load_parameters(
+        &params_dir.join("sapling-spend.params"),
+        &params_dir.join("sapling-output.params"),
+        Some(&params_dir.join("sprout-groth16.params")),
+    )
+}
+
 pub fn load_parameters(
     spend_path: &Path,
     output_path: &Path,
@@ -31,70 +183,382 @@ pub fn load_parameters(
     PreparedVerifyingKey<Bls12>,
     Option<PreparedVerifyingKey<Bls12>>,
 ) {
-    // Sapling circuit hashes
-    const SAPLING_SPEND_HASH: &str = "25fd9a0d1c1be0526c14662947ae95b758fe9f3d7fb7f55e9b4437830dcc6215a7ce3ea465914b157715b7a4d681389ea4aa84438190e185d5e4c93574d3a19a";
-    const SAPLING_OUTPUT_HASH: &str = "a1cb23b93256adce5bce2cb09cefbc96a1d16572675ceb691e9a3626ec15b5b546926ff1c536cfe3a9df07d796b32fdfc3e5d99d65567257bf286cd2858d71a6";
-    const SPROUT_HASH: &str = "_";
-
-    // Load from each of the paths
-    let spend_fs = File::open(spend_path).expect("couldn't load Sapling spend parameters file");
-    let output_fs = File::open(output_path).expect("couldn't load Sapling output parameters file");
-    let sprout_fs =
-        sprout_path.map(|p| File::open(p).expect("couldn't load Sprout groth16 parameters file"));
-
-    let mut spend_fs = hashreader::HashReader::new(BufReader::with_capacity(1024 * 1024, spend_fs));
-    let mut output_fs =
-        hashreader::HashReader::new(BufReader::with_capacity(1024 * 1024, output_fs));
-    let mut sprout_fs =
-        sprout_fs.map(|fs| hashreader::HashReader::new(BufReader::with_capacity(1024 * 1024, fs)));
-
-    // Deserialize params
-    let spend_params = Parameters::<Bls12>::read(&mut spend_fs, false)
-        .expect("couldn't deserialize Sapling spend parameters file");
-    let output_params = Parameters::<Bls12>::read(&mut output_fs, false)
-        .expect("couldn't deserialize Sapling spend parameters file");
-
-    // We only deserialize the verifying key for the Sprout parameters, which
-    // appears at the beginning of the parameter file. The rest is loaded
-    // during proving time.
-    let sprout_vk = sprout_fs.as_mut().map(|mut fs| {
-        VerifyingKey::<Bls12>::read(&mut fs)
-            .expect("couldn't deserialize Sprout Groth16 verifying key")
-    });
-
-    // There is extra stuff (the transcript) at the end of the parameter file which is
-    // used to verify the parameter validity, but we're not interested in that. We do
-    // want to read it, though, so that the BLAKE2b computed afterward is consistent
-    // with `b2sum` on the files.
-    let mut sink = io::sink();
-    io::copy(&mut spend_fs, &mut sink)
-        .expect("couldn't finish reading Sapling spend parameter file");
-    io::copy(&mut output_fs, &mut sink)
-        .expect("couldn't finish reading Sapling output parameter file");
-    if let Some(mut sprout_fs) = sprout_fs.as_mut() {
-        io::copy(&mut sprout_fs, &mut sink)
-            .expect("couldn't finish reading Sprout groth16 parameter file");
+    try_load_parameters(spend_path, output_path, sprout_path)
+        .expect("couldn't load Sapling and Sprout groth16 parameters")
+}
+
+/// Loads the zk-SNARK parameters from the given file paths, returning an error rather
+/// than panicking if a file is missing, can't be deserialized, or doesn't match the
+/// expected hash.
+pub fn try_load_parameters(
+    spend_path: &Path,
+    output_path: &Path,
+    sprout_path: Option<&Path>,
+) -> Result<LoadedParameters, ParameterError> {
+    try_load_parameters_with_hashes(
+        spend_path,
+        output_path,
+        sprout_path,
+        &ExpectedHashes::default(),
+    )
+}
+
+/// Like [`try_load_parameters`], but checks the loaded parameters against
+/// `expected_hashes` instead of the built-in mainnet hashes. This allows loading an
+/// alternative parameter set, such as a test-circuit used by regtest or integration
+/// tests, without recompiling this crate.
+pub fn try_load_parameters_with_hashes(
+    spend_path: &Path,
+    output_path: &Path,
+    sprout_path: Option<&Path>,
+    expected_hashes: &ExpectedHashes,
+) -> Result<LoadedParameters, ParameterError> {
+    let (spend_fs, output_fs, sprout_fs) =
+        open_params_files(spend_path, output_path, sprout_path)?;
+
+    parse_parameters_with_hashes(spend_fs, output_fs, sprout_fs, expected_hashes)
+}
+
+/// Parses Sapling spend and output parameters, and optionally Sprout parameters, from
+/// readers rather than file paths, so that consumers who embed the parameters (e.g. as
+/// a byte slice baked into a binary) don't need to touch the filesystem.
+pub fn parse_parameters<R: io::Read>(
+    spend_fs: R,
+    output_fs: R,
+    sprout_fs: Option<R>,
+) -> Result<LoadedParameters, ParameterError> {
+    parse_parameters_with_hashes(spend_fs, output_fs, sprout_fs, &ExpectedHashes::default())
+}
+
+/// Like [`parse_parameters`], but checks the parsed parameters against
+/// `expected_hashes` instead of the built-in mainnet hashes.
+pub fn parse_parameters_with_hashes<R: io::Read>(
+    spend_fs: R,
+    output_fs: R,
+    sprout_fs: Option<R>,
+    expected_hashes: &ExpectedHashes,
+) -> Result<LoadedParameters, ParameterError> {
+    let parsed = parse_groth16_params(spend_fs, output_fs, sprout_fs, expected_hashes, false)?;
+
+    Ok((
+        parsed
+            .spend_params
+            .expect("Parameters are always returned when verify_only is false"),
+        parsed.spend_vk,
+        parsed
+            .output_params
+            .expect("Parameters are always returned when verify_only is false"),
+        parsed.output_vk,
+        parsed.sprout_vk,
+    ))
+}
+
+/// Loads only the prepared verifying keys for Sapling spend and output, and optionally
+/// Sprout, from the given file paths. As with [`load_parameters`], `sprout_path` may be
+/// `None` without that being an error: a verification-only consumer that doesn't care
+/// about Sprout never needs the file to be present.
+pub fn load_verifying_keys(
+    spend_path: &Path,
+    output_path: &Path,
+    sprout_path: Option<&Path>,
+) -> (
+    PreparedVerifyingKey<Bls12>,
+    PreparedVerifyingKey<Bls12>,
+    Option<PreparedVerifyingKey<Bls12>>,
+) {
+    try_load_verifying_keys(spend_path, output_path, sprout_path)
+        .expect("couldn't load Sapling and Sprout groth16 verifying keys")
+}
+
+/// Loads only the prepared verifying keys for Sapling spend and output, and optionally
+/// Sprout, returning an error rather than panicking if a file is missing, can't be
+/// deserialized, or doesn't match the expected hash.
+pub fn try_load_verifying_keys(
+    spend_path: &Path,
+    output_path: &Path,
+    sprout_path: Option<&Path>,
+) -> Result<
+    (
+        PreparedVerifyingKey<Bls12>,
+        PreparedVerifyingKey<Bls12>,
+        Option<PreparedVerifyingKey<Bls12>>,
+    ),
+    ParameterError,
+> {
+    try_load_verifying_keys_with_hashes(
+        spend_path,
+        output_path,
+        sprout_path,
+        &ExpectedHashes::default(),
+    )
+}
+
+/// Like [`try_load_verifying_keys`], but checks the loaded verifying keys against
+/// `expected_hashes` instead of the built-in mainnet hashes.
+pub fn try_load_verifying_keys_with_hashes(
+    spend_path: &Path,
+    output_path: &Path,
+    sprout_path: Option<&Path>,
+    expected_hashes: &ExpectedHashes,
+) -> Result<
+    (
+        PreparedVerifyingKey<Bls12>,
+        PreparedVerifyingKey<Bls12>,
+        Option<PreparedVerifyingKey<Bls12>>,
+    ),
+    ParameterError,
+> {
+    let (spend_fs, output_fs, sprout_fs) =
+        open_params_files(spend_path, output_path, sprout_path)?;
+
+    parse_verifying_keys_with_hashes(spend_fs, output_fs, sprout_fs, expected_hashes)
+}
+
+/// Parses only the verifying keys for Sapling spend and output, and optionally Sprout,
+/// from readers, without deserializing the proving parameters that follow each verifying
+/// key in the parameter file. This avoids the memory cost of the (much larger) proving
+/// parameters for consumers that only verify proofs. The rest of each file is still
+/// read in full, so that its hash can be checked.
+pub fn parse_verifying_keys<R: io::Read>(
+    spend_fs: R,
+    output_fs: R,
+    sprout_fs: Option<R>,
+) -> Result<
+    (
+        PreparedVerifyingKey<Bls12>,
+        PreparedVerifyingKey<Bls12>,
+        Option<PreparedVerifyingKey<Bls12>>,
+    ),
+    ParameterError,
+> {
+    parse_verifying_keys_with_hashes(spend_fs, output_fs, sprout_fs, &ExpectedHashes::default())
+}
+
+/// Like [`parse_verifying_keys`], but checks the parsed verifying keys against
+/// `expected_hashes` instead of the built-in mainnet hashes.
+pub fn parse_verifying_keys_with_hashes<R: io::Read>(
+    spend_fs: R,
+    output_fs: R,
+    sprout_fs: Option<R>,
+    expected_hashes: &ExpectedHashes,
+) -> Result<
+    (
+        PreparedVerifyingKey<Bls12>,
+        PreparedVerifyingKey<Bls12>,
+        Option<PreparedVerifyingKey<Bls12>>,
+    ),
+    ParameterError,
+> {
+    let parsed = parse_groth16_params(spend_fs, output_fs, sprout_fs, expected_hashes, true)?;
+
+    Ok((parsed.spend_vk, parsed.output_vk, parsed.sprout_vk))
+}
+
+/// Opens the Sapling spend, output, and (optionally) Sprout parameter files, wrapping
+/// each in a large read-ahead [`BufReader`] the way [`try_load_parameters_with_hashes`]
+/// and [`try_load_verifying_keys_with_hashes`] both need.
+fn open_params_files(
+    spend_path: &Path,
+    output_path: &Path,
+    sprout_path: Option<&Path>,
+) -> Result<(BufReader<File>, BufReader<File>, Option<BufReader<File>>), ParameterError> {
+    let spend_fs = File::open(spend_path).map_err(ParameterError::Io)?;
+    let output_fs = File::open(output_path).map_err(ParameterError::Io)?;
+    let sprout_fs = sprout_path
+        .map(File::open)
+        .transpose()
+        .map_err(ParameterError::Io)?;
+
+    Ok((
+        BufReader::with_capacity(1024 * 1024, spend_fs),
+        BufReader::with_capacity(1024 * 1024, output_fs),
+        sprout_fs.map(|fs| BufReader::with_capacity(1024 * 1024, fs)),
+    ))
+}
+
+/// The pieces [`parse_groth16_params`] can produce, covering both the full (proving +
+/// verifying) parameters and the verifying-keys-only subset.
+struct ParsedGroth16Params {
+    spend_params: Option<Parameters<Bls12>>,
+    spend_vk: PreparedVerifyingKey<Bls12>,
+    output_params: Option<Parameters<Bls12>>,
+    output_vk: PreparedVerifyingKey<Bls12>,
+    sprout_vk: Option<PreparedVerifyingKey<Bls12>>,
+}
+
+/// The reader-based core shared by [`parse_parameters_with_hashes`] and
+/// [`parse_verifying_keys_with_hashes`].
+///
+/// Each reader is wrapped in a [`hashreader::HashReader`] so that, after deserializing
+/// the parameters we need, we can drain the rest of the transcript and verify its
+/// BLAKE2b hash against `expected_hashes`. When `verify_only` is set, only the
+/// verifying key prefix of each file is deserialized (as has always been done for
+/// Sprout) and `spend_params`/`output_params` come back `None`, so the Sapling proving
+/// parameters never need to be held in memory. In either mode the Sprout reader may be
+/// absent, in which case `sprout_vk` is `None`.
+fn parse_groth16_params<R: io::Read>(
+    spend_fs: R,
+    output_fs: R,
+    sprout_fs: Option<R>,
+    expected_hashes: &ExpectedHashes,
+    verify_only: bool,
+) -> Result<ParsedGroth16Params, ParameterError> {
+    let mut spend_fs = hashreader::HashReader::new(spend_fs);
+    let mut output_fs = hashreader::HashReader::new(output_fs);
+    let mut sprout_fs = sprout_fs.map(hashreader::HashReader::new);
+
+    let (spend_params, spend_vk) = read_spend_or_output(&mut spend_fs, verify_only)?;
+    let (output_params, output_vk) = read_spend_or_output(&mut output_fs, verify_only)?;
+
+    // We only ever deserialize the verifying key for the Sprout parameters, which
+    // appears at the beginning of the parameter file. The rest is loaded during
+    // proving time.
+    let sprout_vk = sprout_fs
+        .as_mut()
+        .map(|mut fs| VerifyingKey::<Bls12>::read(&mut fs).map_err(ParameterError::Deserialization))
+        .transpose()?
+        .map(|vk| prepare_verifying_key(&vk));
+
+    check_hash(spend_fs, ParameterKind::SaplingSpend, expected_hashes.spend)?;
+    check_hash(output_fs, ParameterKind::SaplingOutput, expected_hashes.output)?;
+    if let Some(sprout_fs) = sprout_fs {
+        check_hash(sprout_fs, ParameterKind::Sprout, expected_hashes.sprout)?;
+    }
+
+    Ok(ParsedGroth16Params {
+        spend_params,
+        spend_vk,
+        output_params,
+        output_vk,
+        sprout_vk,
+    })
+}
+
+/// Reads a Sapling spend or output parameter file, either in full (so it can later be
+/// used for proving) or just its verifying key (so a much smaller footprint suffices
+/// when only verification is needed).
+fn read_spend_or_output<R: io::Read>(
+    fs: &mut hashreader::HashReader<R>,
+    verify_only: bool,
+) -> Result<(Option<Parameters<Bls12>>, PreparedVerifyingKey<Bls12>), ParameterError> {
+    if verify_only {
+        let vk = VerifyingKey::<Bls12>::read(fs).map_err(ParameterError::Deserialization)?;
+        Ok((None, prepare_verifying_key(&vk)))
+    } else {
+        let params =
+            Parameters::<Bls12>::read(fs, false).map_err(ParameterError::Deserialization)?;
+        let vk = prepare_verifying_key(&params.vk);
+        Ok((Some(params), vk))
+    }
+}
+
+/// Drains the remainder of a parameter file's transcript (so that the BLAKE2b hash
+/// computed over it is consistent with `b2sum` on the file) and checks it against the
+/// hash we expect for that parameter kind.
+fn check_hash<R: io::Read>(
+    mut fs: hashreader::HashReader<R>,
+    kind: ParameterKind,
+    expected: &str,
+) -> Result<(), ParameterError> {
+    io::copy(&mut fs, &mut io::sink()).map_err(ParameterError::Io)?;
+
+    let actual = fs.into_hash();
+    if actual != expected {
+        return Err(ParameterError::InvalidHash {
+            kind,
+            expected: expected.to_owned(),
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_params_folder, ExpectedHashes, ParameterError, ParameterKind};
+
+    #[test]
+    fn expected_hashes_default_is_mainnet() {
+        assert_eq!(ExpectedHashes::default(), ExpectedHashes::mainnet());
     }
 
-    if spend_fs.into_hash() != SAPLING_SPEND_HASH {
-        panic!("Sapling spend parameter file is not correct, please clean your `~/.zcash-params/` and re-run `fetch-params`.");
+    #[test]
+    fn expected_hashes_mainnet_matches_known_digests() {
+        let hashes = ExpectedHashes::mainnet();
+        assert_eq!(hashes.spend, super::SAPLING_SPEND_HASH);
+        assert_eq!(hashes.output, super::SAPLING_OUTPUT_HASH);
+        assert_eq!(hashes.sprout, super::SPROUT_HASH);
     }
 
-    if output_fs.into_hash() != SAPLING_OUTPUT_HASH {
-        panic!("Sapling output parameter file is not correct, please clean your `~/.zcash-params/` and re-run `fetch-params`.");
+    #[test]
+    fn parameter_kind_display() {
+        assert_eq!(ParameterKind::SaplingSpend.to_string(), "Sapling spend");
+        assert_eq!(ParameterKind::SaplingOutput.to_string(), "Sapling output");
+        assert_eq!(ParameterKind::Sprout.to_string(), "Sprout groth16");
+    }
+
+    #[test]
+    fn parameter_error_invalid_hash_display_has_no_filesystem_advice() {
+        let err = ParameterError::InvalidHash {
+            kind: ParameterKind::SaplingSpend,
+            expected: "aaaa".to_owned(),
+            actual: "bbbb".to_owned(),
+        };
+        let message = err.to_string();
+
+        assert_eq!(
+            message,
+            "Sapling spend parameter data is not correct (expected hash aaaa, found bbbb)"
+        );
+        assert!(!message.contains("zcash-params"));
+        assert!(!message.contains("fetch-params"));
     }
 
-    if sprout_fs
-        .map(|fs| fs.into_hash() != SPROUT_HASH)
-        .unwrap_or(false)
-    {
-        panic!("Sprout groth16 parameter file is not correct, please clean your `~/.zcash-params/` and re-run `fetch-params`.");
+    // default_params_folder_uses_appdata_on_windows and default_params_folder_uses_home_elsewhere
+    // each mutate a different, platform-specific environment variable (APPDATA vs. HOME) and are
+    // mutually exclusive via #[cfg], so they can't race each other today. Neither is serialized
+    // against cargo test's default multi-threaded runner, though: a future test that touches the
+    // same variable on the same platform would need an explicit lock (e.g. a `Mutex<()>` guard) to
+    // avoid flakiness, since nothing here enforces single-threaded execution.
+
+    #[test]
+    #[cfg(windows)]
+    fn default_params_folder_uses_appdata_on_windows() {
+        let original = std::env::var_os("APPDATA");
+
+        std::env::set_var("APPDATA", r"C:\Users\test\AppData\Roaming");
+        assert_eq!(
+            default_params_folder(),
+            Some(std::path::PathBuf::from(
+                r"C:\Users\test\AppData\Roaming\ZcashParams"
+            ))
+        );
+
+        std::env::remove_var("APPDATA");
+        assert_eq!(default_params_folder(), None);
+
+        if let Some(original) = original {
+            std::env::set_var("APPDATA", original);
+        }
     }
 
-    // Prepare verifying keys
-    let spend_vk = prepare_verifying_key(&spend_params.vk);
-    let output_vk = prepare_verifying_key(&output_params.vk);
-    let sprout_vk = sprout_vk.map(|vk| prepare_verifying_key(&vk));
+    #[test]
+    #[cfg(not(windows))]
+    fn default_params_folder_uses_home_elsewhere() {
+        let original = std::env::var_os("HOME");
 
-    (spend_params, spend_vk, output_params, output_vk, sprout_vk)
+        std::env::set_var("HOME", "/home/test");
+        assert_eq!(
+            default_params_folder(),
+            Some(std::path::PathBuf::from("/home/test/.zcash-params"))
+        );
+
+        std::env::remove_var("HOME");
+        assert_eq!(default_params_folder(), None);
+
+        if let Some(original) = original {
+            std::env::set_var("HOME", original);
+        }
+    }
 }